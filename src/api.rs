@@ -3,6 +3,10 @@ use crate::sign::*;
 use crate::SEEDBYTES;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 use constant_time_eq::constant_time_eq;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use bs58::{encode as bs58_encode, decode as bs58_decode};
+use rand_core::{CryptoRng, RngCore};
+use signature::{Error as SignatureError, Signer, SignatureEncoding, Verifier};
 #[derive(Clone, PartialEq, Eq, Hash, Zeroize, ZeroizeOnDrop)]
 pub struct Keypair {
   pub public: [u8; PUBLICKEYBYTES],
@@ -60,6 +64,38 @@ impl Keypair {
     crypto_sign_keypair(&mut public, &mut secret, Some(&seed));
     Keypair { public, secret }
   }
+  /// Generate a keypair, drawing randomness from a caller-supplied CSPRNG
+  /// instead of the OS RNG
+  ///
+  /// Useful for deterministic, reproducible test vectors when `rng` is
+  /// seeded from a fixed value.
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// # use rand_core::{CryptoRng, RngCore};
+  /// # struct FakeRng(u8);
+  /// # impl RngCore for FakeRng {
+  /// #   fn next_u32(&mut self) -> u32 { self.0 as u32 }
+  /// #   fn next_u64(&mut self) -> u64 { self.0 as u64 }
+  /// #   fn fill_bytes(&mut self, dest: &mut [u8]) { dest.fill(self.0); }
+  /// #   fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> { self.fill_bytes(dest); Ok(()) }
+  /// # }
+  /// # impl CryptoRng for FakeRng {}
+  /// let mut rng = FakeRng(7);
+  /// let keys = Keypair::generate_with_rng(&mut rng);
+  /// let keys2 = Keypair::generate_with_rng(&mut FakeRng(7));
+  /// assert!(keys.compare_secrets(&keys2));
+  /// ```
+  pub fn generate_with_rng<R: CryptoRng + RngCore>(
+    rng: &mut R,
+  ) -> Keypair {
+    let mut seed = [0u8; SEEDBYTES];
+    rng.fill_bytes(&mut seed);
+    let keys = Keypair::generate_with_seed(seed);
+    seed.zeroize();
+    keys
+  }
   /// Generates a keypair for signing and verification
   ///
   /// Example:
@@ -85,12 +121,255 @@ impl Keypair {
   /// let msg = "Hello".as_bytes();
   /// let sig = keys.sign(&msg);
   /// assert!(sig.len() == SIGNBYTES);
-  /// ```  
+  /// ```
   pub fn sign(&self, msg: &[u8]) -> [u8; SIGNBYTES] {
     let mut sig = [0u8; SIGNBYTES];
     crypto_sign_signature(&mut sig, msg, &self.secret);
     sig
   }
+
+  /// Sign `msg` bound to an application-specific context string, per the
+  /// FIPS 204 domain-separation framing for the pure variant
+  ///
+  /// `context` must not exceed 255 bytes. Unlike [`Keypair::sign`], this
+  /// binds `msg` behind the `0x00 || len(context) || context` prefix FIPS
+  /// 204 defines for domain separation, so signatures produced here are
+  /// only verifiable with [`verify_with_context`] against the same
+  /// context, never with plain [`verify`].
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// # let keys = Keypair::generate();
+  /// let msg = "Hello".as_bytes();
+  /// let sig = keys.sign_with_context(&msg, b"example-protocol").unwrap();
+  /// assert!(verify_with_context(&sig, &msg, b"example-protocol", &keys.public).is_ok());
+  /// ```
+  pub fn sign_with_context(
+    &self,
+    msg: &[u8],
+    context: &[u8],
+  ) -> Result<[u8; SIGNBYTES], SignError> {
+    if context.len() > 255 {
+      return Err(SignError::Input);
+    }
+    let framed = frame_message(msg, context);
+    let mut sig = [0u8; SIGNBYTES];
+    crypto_sign_signature(&mut sig, &framed, &self.secret);
+    Ok(sig)
+  }
+
+  /// Serialize the keypair as `public || secret`
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// let keys = Keypair::generate();
+  /// let bytes = keys.to_bytes();
+  /// let restored = Keypair::from_bytes(&bytes).unwrap();
+  /// assert!(keys.compare_secrets(&restored));
+  /// ```
+  pub fn to_bytes(&self) -> [u8; PUBLICKEYBYTES + SECRETKEYBYTES] {
+    let mut bytes = [0u8; PUBLICKEYBYTES + SECRETKEYBYTES];
+    bytes[..PUBLICKEYBYTES].copy_from_slice(&self.public);
+    bytes[PUBLICKEYBYTES..].copy_from_slice(&self.secret);
+    bytes
+  }
+
+  /// Reconstruct a keypair previously serialized with [`Keypair::to_bytes`]
+  ///
+  /// Fails with [`SignError::Input`] if `bytes` isn't exactly
+  /// `PUBLICKEYBYTES + SECRETKEYBYTES` long.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Keypair, SignError> {
+    if bytes.len() != PUBLICKEYBYTES + SECRETKEYBYTES {
+      return Err(SignError::Input);
+    }
+    let mut public = [0u8; PUBLICKEYBYTES];
+    let mut secret = [0u8; SECRETKEYBYTES];
+    public.copy_from_slice(&bytes[..PUBLICKEYBYTES]);
+    secret.copy_from_slice(&bytes[PUBLICKEYBYTES..]);
+    Ok(Keypair { public, secret })
+  }
+
+  /// Serialize the keypair to a base58-encoded string
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// let keys = Keypair::generate();
+  /// let encoded = keys.to_base58_string();
+  /// let restored = Keypair::from_base58_string(&encoded).unwrap();
+  /// assert!(keys.compare_secrets(&restored));
+  /// ```
+  pub fn to_base58_string(&self) -> String {
+    bs58_encode(self.to_bytes()).into_string()
+  }
+
+  /// Reconstruct a keypair from a string produced by [`Keypair::to_base58_string`]
+  ///
+  /// Fails with [`SignError::Input`] if `s` isn't valid base58, or decodes
+  /// to something other than `PUBLICKEYBYTES + SECRETKEYBYTES` bytes.
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// assert!(Keypair::from_base58_string("not valid base58! 0OIl").is_err());
+  /// assert!(Keypair::from_base58_string(&bs58::encode([0u8; 4]).into_string()).is_err());
+  /// ```
+  pub fn from_base58_string(s: &str) -> Result<Keypair, SignError> {
+    let mut decoded = bs58_decode(s).into_vec().map_err(|_| SignError::Input)?;
+    let keys = Keypair::from_bytes(&decoded);
+    decoded.zeroize();
+    keys
+  }
+
+  /// Serialize the keypair to a base64-encoded string
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// let keys = Keypair::generate();
+  /// let encoded = keys.to_base64_string();
+  /// let restored = Keypair::from_base64_string(&encoded).unwrap();
+  /// assert!(keys.compare_secrets(&restored));
+  /// ```
+  pub fn to_base64_string(&self) -> String {
+    STANDARD.encode(self.to_bytes())
+  }
+
+  /// Reconstruct a keypair from a string produced by [`Keypair::to_base64_string`]
+  ///
+  /// Fails with [`SignError::Input`] if `s` isn't valid base64, or decodes
+  /// to something other than `PUBLICKEYBYTES + SECRETKEYBYTES` bytes.
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// # use base64::Engine;
+  /// assert!(Keypair::from_base64_string("not valid base64!!").is_err());
+  /// assert!(Keypair::from_base64_string(&base64::engine::general_purpose::STANDARD.encode([0u8; 4])).is_err());
+  /// ```
+  pub fn from_base64_string(s: &str) -> Result<Keypair, SignError> {
+    let mut decoded = STANDARD.decode(s).map_err(|_| SignError::Input)?;
+    let keys = Keypair::from_bytes(&decoded);
+    decoded.zeroize();
+    keys
+  }
+
+  /// Sign `msg` and return the signature prepended to the message, as a
+  /// single transportable blob (`sig || msg`)
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// let keys = Keypair::generate();
+  /// let signed = keys.sign_attached("Hello".as_bytes());
+  /// let msg = open(&signed, &keys.public).unwrap();
+  /// assert_eq!(msg, "Hello".as_bytes());
+  /// ```
+  pub fn sign_attached(&self, msg: &[u8]) -> Vec<u8> {
+    let sig = self.sign(msg);
+    let mut signed = Vec::with_capacity(SIGNBYTES + msg.len());
+    signed.extend_from_slice(&sig);
+    signed.extend_from_slice(msg);
+    signed
+  }
+
+  /// Encode the public key as a `did:key`-style multikey: a multicodec
+  /// varint prefix identifying the ML-DSA key type, followed by the raw
+  /// key bytes, base58btc-encoded and prefixed with `z`
+  ///
+  /// Example:
+  /// ```
+  /// # use pqc_dilithium::*;
+  /// let keys = Keypair::generate();
+  /// let multikey = keys.public_multikey();
+  /// assert!(multikey.starts_with('z'));
+  /// assert_eq!(public_key_from_multikey(&multikey).unwrap(), keys.public);
+  /// ```
+  ///
+  /// Known-answer check that the multicodec varint prefix is the `mode3`
+  /// (ML-DSA-65) code point `0x1204`, not just whatever this crate's own
+  /// encoder/decoder happen to agree on:
+  /// ```
+  /// # #[cfg(not(any(feature = "mode2", feature = "mode5")))]
+  /// # {
+  /// # use pqc_dilithium::*;
+  /// let keys = Keypair::generate();
+  /// let multikey = keys.public_multikey();
+  /// let decoded = bs58::decode(&multikey[1..]).into_vec().unwrap();
+  /// assert_eq!(&decoded[..2], &[0x84, 0x24]);
+  /// # }
+  /// ```
+  pub fn public_multikey(&self) -> String {
+    let mut bytes = Vec::with_capacity(self.public.len() + 2);
+    encode_varint(ML_DSA_MULTICODEC, &mut bytes);
+    bytes.extend_from_slice(&self.public);
+    format!("z{}", bs58_encode(bytes).into_string())
+  }
+}
+
+/// Multicodec code point for ML-DSA public keys, as used by `did:key`
+///
+/// The multicodec table defines a distinct code point per ML-DSA parameter
+/// set, so this must track whichever `mode2`/`mode3`/`mode5` feature
+/// selects `PUBLICKEYBYTES` at compile time.
+#[cfg(feature = "mode2")]
+const ML_DSA_MULTICODEC: u64 = 0x1203; // ml-dsa-44-pub
+#[cfg(any(feature = "mode3", not(any(feature = "mode2", feature = "mode5"))))]
+const ML_DSA_MULTICODEC: u64 = 0x1204; // ml-dsa-65-pub
+#[cfg(feature = "mode5")]
+const ML_DSA_MULTICODEC: u64 = 0x1205; // ml-dsa-87-pub
+
+fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+  loop {
+    let byte = (value & 0x7f) as u8;
+    value >>= 7;
+    if value != 0 {
+      buf.push(byte | 0x80);
+    } else {
+      buf.push(byte);
+      break;
+    }
+  }
+}
+
+fn decode_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+  let mut result: u64 = 0;
+  let mut shift = 0;
+  for (i, &byte) in bytes.iter().enumerate() {
+    result |= ((byte & 0x7f) as u64) << shift;
+    if byte & 0x80 == 0 {
+      return Some((result, i + 1));
+    }
+    shift += 7;
+    if shift >= 64 {
+      return None;
+    }
+  }
+  None
+}
+
+/// Decode a public key from a `did:key`-style multikey produced by
+/// [`Keypair::public_multikey`]
+///
+/// Fails with [`SignError::Input`] if the string isn't `z`-prefixed valid
+/// base58btc, the multicodec prefix doesn't match ML-DSA, or the remaining
+/// bytes aren't exactly `PUBLICKEYBYTES` long.
+pub fn public_key_from_multikey(s: &str) -> Result<[u8; PUBLICKEYBYTES], SignError> {
+  let encoded = s.strip_prefix('z').ok_or(SignError::Input)?;
+  let bytes = bs58_decode(encoded).into_vec().map_err(|_| SignError::Input)?;
+  let (code, prefix_len) = decode_varint(&bytes).ok_or(SignError::Input)?;
+  if code != ML_DSA_MULTICODEC {
+    return Err(SignError::Input);
+  }
+  let rest = &bytes[prefix_len..];
+  if rest.len() != PUBLICKEYBYTES {
+    return Err(SignError::Input);
+  }
+  let mut public = [0u8; PUBLICKEYBYTES];
+  public.copy_from_slice(rest);
+  Ok(public)
 }
 
 /// Verify signature using keypair
@@ -113,3 +392,129 @@ pub fn verify(
   }
   crypto_sign_verify(&sig, &msg, public_key)
 }
+
+/// Verify a signature produced by [`Keypair::sign_with_context`] against the
+/// same context string
+///
+/// Fails with [`SignError::Input`] if `context` exceeds 255 bytes or `sig`
+/// isn't `SIGNBYTES` long. This does not accept signatures produced by the
+/// plain [`Keypair::sign`]/[`verify`] pair, which carry no context framing.
+pub fn verify_with_context(
+  sig: &[u8],
+  msg: &[u8],
+  context: &[u8],
+  public_key: &[u8],
+) -> Result<(), SignError> {
+  if context.len() > 255 {
+    return Err(SignError::Input);
+  }
+  if sig.len() != SIGNBYTES {
+    return Err(SignError::Input);
+  }
+  let framed = frame_message(msg, context);
+  crypto_sign_verify(&sig, &framed, public_key)
+}
+
+/// Apply the FIPS 204 pure-variant domain-separation framing: the octet
+/// `0x00`, the octet-length of `context`, then `context` itself, all ahead
+/// of the normal message bytes
+fn frame_message(msg: &[u8], context: &[u8]) -> Vec<u8> {
+  let mut framed = Vec::with_capacity(2 + context.len() + msg.len());
+  framed.push(0x00);
+  framed.push(context.len() as u8);
+  framed.extend_from_slice(context);
+  framed.extend_from_slice(msg);
+  framed
+}
+
+/// Recover the message from a blob produced by [`Keypair::sign_attached`],
+/// verifying it against `public_key` in the process
+///
+/// Example:
+/// ```
+/// # use pqc_dilithium::*;
+/// # let keys = Keypair::generate();
+/// # let signed = keys.sign_attached("Hello".as_bytes());
+/// let msg = open(&signed, &keys.public).unwrap();
+/// assert_eq!(msg, "Hello".as_bytes());
+/// ```
+pub fn open(signed: &[u8], public_key: &[u8]) -> Result<Vec<u8>, SignError> {
+  if signed.len() < SIGNBYTES {
+    return Err(SignError::Input);
+  }
+  let (sig, msg) = signed.split_at(SIGNBYTES);
+  verify(sig, msg, public_key)?;
+  Ok(msg.to_vec())
+}
+
+/// A detached signature, for interop with the `signature` crate's
+/// [`signature::Signer`]/[`signature::Verifier`] traits
+///
+/// Example:
+/// ```
+/// # use pqc_dilithium::*;
+/// # use signature::{Signer, Verifier};
+/// let keys = Keypair::generate();
+/// let sig: Signature = keys.try_sign("Hello".as_bytes()).unwrap();
+/// assert!(keys.verify("Hello".as_bytes(), &sig).is_ok());
+///
+/// let verifying_key = VerifyingKey::new(keys.public);
+/// assert!(verifying_key.verify("Hello".as_bytes(), &sig).is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature([u8; SIGNBYTES]);
+
+impl SignatureEncoding for Signature {
+  type Repr = [u8; SIGNBYTES];
+
+  fn to_bytes(&self) -> Self::Repr {
+    self.0
+  }
+}
+
+impl From<Signature> for [u8; SIGNBYTES] {
+  fn from(sig: Signature) -> [u8; SIGNBYTES] {
+    sig.0
+  }
+}
+
+impl TryFrom<&[u8]> for Signature {
+  type Error = SignatureError;
+  fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    if bytes.len() != SIGNBYTES {
+      return Err(SignatureError::new());
+    }
+    let mut sig = [0u8; SIGNBYTES];
+    sig.copy_from_slice(bytes);
+    Ok(Signature(sig))
+  }
+}
+
+impl Signer<Signature> for Keypair {
+  fn try_sign(&self, msg: &[u8]) -> Result<Signature, SignatureError> {
+    Ok(Signature(self.sign(msg)))
+  }
+}
+
+impl Verifier<Signature> for Keypair {
+  fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+    verify(&signature.0, msg, &self.public).map_err(|_| SignatureError::new())
+  }
+}
+
+/// A public key on its own, for relying parties that only need to verify
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyingKey([u8; PUBLICKEYBYTES]);
+
+impl VerifyingKey {
+  /// Wrap a raw public key for use with [`signature::Verifier`]
+  pub fn new(public_key: [u8; PUBLICKEYBYTES]) -> Self {
+    VerifyingKey(public_key)
+  }
+}
+
+impl Verifier<Signature> for VerifyingKey {
+  fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+    verify(&signature.0, msg, &self.0).map_err(|_| SignatureError::new())
+  }
+}